@@ -1,7 +1,17 @@
 //! A procedural macro that allows you to derive `std::ops::Deref` and `std::ops::DerefMut` for
 //! your structs. This macro can only be derived on structs **with atleast one field**. You can
 //! specify which field you want to be deref'ed to with the `#[deref]` and allow mutable
-//! dereferencing with `#[deref(mutable)]`.
+//! dereferencing with `#[deref(mutable)]`. Add `#[deref(forward)]` (combinable as
+//! `#[deref(mutable, forward)]`) to delegate to the field's own `Deref`/`DerefMut` impl instead
+//! of stopping at the field itself.
+//!
+//! For a struct with exactly one field, `#[deref]` can be omitted entirely and that field is
+//! used automatically; put `#[deref(mutable)]` on the struct itself to still derive `DerefMut`
+//! in that case.
+//!
+//! `Deref` and `DerefMut` can also be derived independently of each other, choosing mutability
+//! by which derive is listed rather than by the `mutable` sub-attribute, e.g.
+//! `#[derive(Deref, DerefMut)]`.
 //!
 //! ## Deriving `std::ops::Deref`
 //! ```ignore
@@ -55,40 +65,69 @@ use syn::*;
 pub fn derefable_derive(input: TokenStream) -> TokenStream {
     let ast = syn::parse(input).unwrap();
 
-    impl_derefable(&ast)
+    match impl_derefable(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(Deref, attributes(deref))]
+pub fn deref_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse(input).unwrap();
+
+    match resolve_field(&ast).map(|field| deref_impl(&ast, &field)) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(DerefMut, attributes(deref))]
+pub fn deref_mut_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse(input).unwrap();
+
+    match resolve_field(&ast).map(|field| deref_mut_impl(&ast, &field)) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// The field a `Deref`/`DerefMut` impl should be generated for, along with the sub-attributes
+/// that were resolved for it.
+struct DerefField {
+    ident: proc_macro2::TokenStream,
+    ty: Type,
+    is_mutable: bool,
+    is_forwarded: bool,
 }
 
-fn impl_derefable(ast: &syn::DeriveInput) -> TokenStream {
+/// Finds the field to deref to, shared by `Derefable`, `Deref` and `DerefMut`.
+///
+/// A field is picked either because it carries an explicit `#[deref]` (or `#[deref(..)]`)
+/// attribute, or, for single-field structs, automatically; in the latter case the struct-level
+/// `#[deref(..)]` attribute (if any) supplies the sub-attributes instead.
+fn resolve_field(ast: &syn::DeriveInput) -> Result<DerefField> {
     let name = &ast.ident;
 
     let data = match ast.data {
         Data::Struct(ref s) => s,
         _ => {
-            // name.span()
-            //     .unstable()
-            //     .error("`#[derive(Derefable)]` is only available for structs")
-            //     .emit();
-
-            // return TokenStream::new()
-            panic!("`#[derive(Derefable)]` is only available for structs")
+            return Err(Error::new_spanned(
+                name,
+                "this derive is only available for structs",
+            ))
         }
     };
 
-    match data.fields {
-        Fields::Unit => {
-            // data.span()
-            //     .unstable()
-            //     .error("`#[derive(Derefable)]` requires a field to be able to deref")
-            //     .emit();
-
-            // return TokenStream::new()
-            panic!("`#[derive(Derefable)]` requires a field to be able to deref")
-        }
-        _ => {}
+    if let Fields::Unit = data.fields {
+        return Err(Error::new_spanned(
+            name,
+            "this derive requires a field to be able to deref",
+        ));
     }
 
     let mut deref_field = None;
     let mut is_field_mutable = false;
+    let mut is_field_forwarded = false;
 
     for (i, field) in data.fields.iter().enumerate() {
         for attribute in &field.attrs {
@@ -98,13 +137,10 @@ fn impl_derefable(ast: &syn::DeriveInput) -> TokenStream {
                         if deref_field.is_none() {
                             deref_field = Some((field.clone(), i as u32));
                         } else {
-                            // name.span()
-                            //     .unstable()
-                            //     .error("Only one field in a struct can be `#[deref]`")
-                            //     .emit();
-
-                            // return TokenStream::new()
-                            panic!("Only one field in a struct can be `#[deref]`")
+                            return Err(Error::new_spanned(
+                                field,
+                                "Only one field in a struct can be `#[deref]`",
+                            ));
                         }
                     }
 
@@ -114,23 +150,22 @@ fn impl_derefable(ast: &syn::DeriveInput) -> TokenStream {
                         ..
                     }) if ident == "deref" => {
                         is_field_mutable = nested.iter().any(|nested_item| match nested_item {
-                            NestedMeta::Meta(m) => match m {
-                                Meta::Word(ident) => ident == "mutable",
-                                _ => false,
-                            },
+                            NestedMeta::Meta(Meta::Word(ident)) => ident == "mutable",
+                            _ => false,
+                        });
+
+                        is_field_forwarded = nested.iter().any(|nested_item| match nested_item {
+                            NestedMeta::Meta(Meta::Word(ident)) => ident == "forward",
                             _ => false,
                         });
 
                         if deref_field.is_none() {
                             deref_field = Some((field.clone(), i as u32));
                         } else {
-                            // name.span()
-                            //     .unstable()
-                            //     .error("Only one field in a struct can be `#[deref]`")
-                            //     .emit();
-
-                            // return TokenStream::new()
-                            panic!("Only one field in a struct can be `#[deref]`")
+                            return Err(Error::new_spanned(
+                                field,
+                                "Only one field in a struct can be `#[deref]`",
+                            ));
                         }
                     }
                     _ => {}
@@ -139,19 +174,42 @@ fn impl_derefable(ast: &syn::DeriveInput) -> TokenStream {
         }
     }
 
-    if deref_field.is_none() {
-        // name.span()
-        //     .unstable()
-        //     .error("`#[derive(Derefable)]` requires one field to be marked `#[deref]`")
-        //     .emit();
+    if deref_field.is_none() && data.fields.iter().count() == 1 {
+        let field = data.fields.iter().next().unwrap();
+        deref_field = Some((field.clone(), 0));
 
-        // return TokenStream::new()
-        panic!("`#[derive(Derefable)]` requires one field to be marked `#[deref]`");
+        for attribute in &ast.attrs {
+            if let Ok(Meta::List(MetaList {
+                ref ident,
+                ref nested,
+                ..
+            })) = attribute.parse_meta()
+            {
+                if ident == "deref" {
+                    is_field_mutable = nested.iter().any(|nested_item| match nested_item {
+                        NestedMeta::Meta(Meta::Word(ident)) => ident == "mutable",
+                        _ => false,
+                    });
+
+                    is_field_forwarded = nested.iter().any(|nested_item| match nested_item {
+                        NestedMeta::Meta(Meta::Word(ident)) => ident == "forward",
+                        _ => false,
+                    });
+                }
+            }
+        }
+    }
+
+    if deref_field.is_none() {
+        return Err(Error::new_spanned(
+            name,
+            "this derive requires one field to be marked `#[deref]`",
+        ));
     }
 
     let (field, index) = deref_field.unwrap();
 
-    let target = field.ty;
+    let ty = field.ty;
     let ident = field
         .ident
         .map(Ident::into_token_stream)
@@ -163,29 +221,102 @@ fn impl_derefable(ast: &syn::DeriveInput) -> TokenStream {
             .into_token_stream()
         });
 
-    let mut_gen = if is_field_mutable {
-        quote! {
-            impl std::ops::DerefMut for #name {
-                fn deref_mut(&mut self) -> &mut Self::Target {
-                    &mut self.#ident
-                }
-            }
-        }
+    Ok(DerefField {
+        ident,
+        ty,
+        is_mutable: is_field_mutable,
+        is_forwarded: is_field_forwarded,
+    })
+}
+
+fn deref_impl(ast: &syn::DeriveInput, field: &DerefField) -> proc_macro2::TokenStream {
+    let name = &ast.ident;
+    let DerefField {
+        ident,
+        ty,
+        is_forwarded,
+        ..
+    } = field;
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let (target, body, where_clause) = if *is_forwarded {
+        let where_clause = match where_clause {
+            Some(where_clause) => quote! { #where_clause, #ty: std::ops::Deref },
+            None => quote! { where #ty: std::ops::Deref },
+        };
+
+        (
+            quote! { <#ty as std::ops::Deref>::Target },
+            quote! { std::ops::Deref::deref(&self.#ident) },
+            where_clause,
+        )
     } else {
-        quote! {}
+        (
+            quote! { #ty },
+            quote! { &self.#ident },
+            quote! { #where_clause },
+        )
     };
 
-    let gen = quote! {
-        impl std::ops::Deref for #name {
+    quote! {
+        impl #impl_generics std::ops::Deref for #name #ty_generics #where_clause {
             type Target = #target;
 
             fn deref(&self) -> &Self::Target {
-                &self.#ident
+                #body
             }
         }
+    }
+}
 
-        #mut_gen
+fn deref_mut_impl(ast: &syn::DeriveInput, field: &DerefField) -> proc_macro2::TokenStream {
+    let name = &ast.ident;
+    let DerefField {
+        ident,
+        ty,
+        is_forwarded,
+        ..
+    } = field;
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let (body, where_clause) = if *is_forwarded {
+        let where_clause = match where_clause {
+            Some(where_clause) => quote! { #where_clause, #ty: std::ops::DerefMut },
+            None => quote! { where #ty: std::ops::DerefMut },
+        };
+
+        (
+            quote! { std::ops::DerefMut::deref_mut(&mut self.#ident) },
+            where_clause,
+        )
+    } else {
+        (quote! { &mut self.#ident }, quote! { #where_clause })
     };
 
-    gen.into()
+    quote! {
+        impl #impl_generics std::ops::DerefMut for #name #ty_generics #where_clause {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                #body
+            }
+        }
+    }
+}
+
+fn impl_derefable(ast: &syn::DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let field = resolve_field(ast)?;
+
+    let deref_gen = deref_impl(ast, &field);
+    let mut_gen = if field.is_mutable {
+        deref_mut_impl(ast, &field)
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        #deref_gen
+
+        #mut_gen
+    })
 }
@@ -1,4 +1,4 @@
-use derefable_derive::Derefable;
+use derefable_derive::{Deref, DerefMut, Derefable};
 
 #[derive(Derefable)]
 struct Foo {
@@ -37,3 +37,97 @@ fn deref_mut() {
 
     assert_eq!(*foo, 10);
 }
+
+#[derive(Derefable)]
+struct Wrapper<T>(#[deref] Vec<T>);
+
+#[test]
+fn generic() {
+    let foo = Wrapper(vec![1, 2, 3]);
+
+    assert_eq!(foo.len(), 3);
+}
+
+#[derive(Derefable)]
+struct Cache<'a, K: std::hash::Hash + Eq> {
+    #[deref]
+    map: std::collections::HashMap<K, &'a str>,
+}
+
+#[test]
+fn generic_with_lifetime_and_bounds() {
+    let mut map = std::collections::HashMap::new();
+    map.insert("key", "value");
+
+    let cache = Cache { map };
+
+    assert_eq!(cache.get("key"), Some(&"value"));
+}
+
+#[derive(Derefable)]
+struct ForwardedString(#[deref(forward)] String);
+
+#[test]
+fn forward() {
+    let foo = ForwardedString(String::from("hello"));
+
+    let s: &str = &foo;
+    assert_eq!(s, "hello");
+}
+
+#[derive(Derefable)]
+struct MutableForwardedString(#[deref(mutable, forward)] String);
+
+#[test]
+fn forward_mutable() {
+    let mut foo = MutableForwardedString(String::from("hello"));
+
+    foo.make_ascii_uppercase();
+
+    assert_eq!(&*foo, "HELLO");
+}
+
+#[derive(Derefable)]
+struct Meters(f64);
+
+#[test]
+fn auto_select_single_field() {
+    let meters = Meters(5.0);
+
+    assert_eq!(*meters, 5.0);
+}
+
+#[derive(Derefable)]
+#[deref(mutable)]
+struct Counter(u32);
+
+#[test]
+fn auto_select_single_field_mutable() {
+    let mut counter = Counter(5);
+
+    *counter += 1;
+
+    assert_eq!(*counter, 6);
+}
+
+#[derive(Deref)]
+struct Id(#[deref] u32);
+
+#[test]
+fn standalone_deref() {
+    let id = Id(5);
+
+    assert_eq!(*id, 5);
+}
+
+#[derive(Deref, DerefMut)]
+struct Both(#[deref] u32);
+
+#[test]
+fn standalone_deref_and_deref_mut() {
+    let mut both = Both(5);
+
+    *both += 1;
+
+    assert_eq!(*both, 6);
+}